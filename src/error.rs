@@ -0,0 +1,58 @@
+//! Structured errors with a human/internal split, mirroring the distinction
+//! Cargo's error module draws between user-facing failures and internal
+//! ("caused by") ones: each variant carries its own process exit code, and
+//! only internal failures get their full causal chain logged.
+
+use log::debug;
+
+/// Exit code used for invocation problems (bad args, no fallback specified).
+const EXIT_USAGE: u8 = 1;
+/// Exit code used for unexpected internal failures (I/O, metadata reads, ...).
+const EXIT_INTERNAL: u8 = 2;
+
+pub(crate) enum TailorError {
+    /// The user did something the tool can't work with: an untailable file
+    /// with no fallback, bad arguments, and the like. The message is written
+    /// to stderr as-is; no exit code but 1.
+    Usage(anyhow::Error),
+    /// Something went wrong that the user didn't directly cause (a read
+    /// failed, a syscall errored). The top-level message is shown to the
+    /// user; the full causal chain is only logged at debug level.
+    Internal(anyhow::Error),
+    /// The fallback command ran and exited non-zero. Its exit code is
+    /// propagated verbatim, matching what running the command directly
+    /// would produce.
+    Fallback(i32),
+}
+
+impl TailorError {
+    pub(crate) fn exit_code(&self) -> u8 {
+        match self {
+            TailorError::Usage(_) => EXIT_USAGE,
+            TailorError::Internal(_) => EXIT_INTERNAL,
+            // Exit codes are a single byte on the platforms tailor targets;
+            // truncate rather than fail to exit at all.
+            TailorError::Fallback(code) => *code as u8,
+        }
+    }
+
+    /// Write this error the way a user should see it: a plain message on
+    /// stderr, no Rust backtrace. Internal errors additionally log their
+    /// full causal chain at debug level for whoever's troubleshooting.
+    pub(crate) fn report(&self) {
+        match self {
+            TailorError::Usage(e) => eprintln!("error: {e}"),
+            TailorError::Internal(e) => {
+                eprintln!("error: {e}");
+                debug!("internal error chain: {e:?}");
+            }
+            TailorError::Fallback(_) => {}
+        }
+    }
+}
+
+impl From<anyhow::Error> for TailorError {
+    fn from(e: anyhow::Error) -> Self {
+        TailorError::Internal(e)
+    }
+}
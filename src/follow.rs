@@ -0,0 +1,187 @@
+//! Streaming follow mode (`-f`), with log-rotation detection.
+//!
+//! Watches a file for appended data and prints it as it arrives, the way
+//! `tail -f` does. Rotation (the file being renamed/recreated, e.g. by
+//! `logrotate`) and truncation are detected by tracking the file's
+//! device/inode and size, and handled by reopening the path from the start.
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher, recommended_watcher};
+use std::fs::{File, metadata};
+use std::io::{Read, Seek, SeekFrom, Write, stdout};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::sync::mpsc::{RecvTimeoutError, channel};
+use std::thread;
+use std::time::Duration;
+
+/// How often to poll `stat` when no native watcher is available, and how
+/// often to re-check the file even while a watcher is active (events can be
+/// coalesced or missed across a rotation).
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Identifies a file by device + inode so rotation (recreation under the same
+/// path) can be told apart from ordinary appends.
+struct FileIdentity {
+    dev: u64,
+    ino: u64,
+}
+
+impl FileIdentity {
+    fn of(path: &Path) -> Result<Self> {
+        let meta =
+            metadata(path).with_context(|| format!("failed to stat {}", path.display()))?;
+        Ok(Self {
+            dev: meta.dev(),
+            ino: meta.ino(),
+        })
+    }
+}
+
+/// Block forever, printing data appended to `file_path` after `offset`.
+///
+/// Prefers a native filesystem watcher (inotify on Linux, kqueue on
+/// BSD/macOS, via the `notify` crate) and falls back to timed `stat` polling
+/// when no native watcher can be installed.
+pub(crate) fn follow_file(file_path: &str, mut offset: u64) -> Result<()> {
+    let path = Path::new(file_path);
+    let mut identity = FileIdentity::of(path)?;
+
+    let (tx, rx) = channel();
+    let mut watcher: Option<RecommendedWatcher> = match recommended_watcher(move |res| {
+        let _ = tx.send(res);
+    }) {
+        Ok(mut watcher) => {
+            let watch_dir = path.parent().filter(|p| !p.as_os_str().is_empty());
+            let watch_target = watch_dir.unwrap_or_else(|| Path::new("."));
+            match watcher.watch(watch_target, RecursiveMode::NonRecursive) {
+                Ok(()) => Some(watcher),
+                Err(e) => {
+                    warn!(
+                        "failed to watch {}, falling back to polling: {e}",
+                        watch_target.display()
+                    );
+                    None
+                }
+            }
+        }
+        Err(e) => {
+            warn!("no native filesystem watcher available, falling back to polling: {e}");
+            None
+        }
+    };
+
+    loop {
+        match watcher {
+            Some(_) => match rx.recv_timeout(POLL_INTERVAL) {
+                Ok(Ok(_event)) => {}
+                Ok(Err(e)) => warn!("filesystem watch error: {e}"),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    warn!("filesystem watcher disconnected, falling back to polling");
+                    watcher = None;
+                }
+            },
+            None => thread::sleep(POLL_INTERVAL),
+        }
+
+        offset = follow_tick(path, &mut identity, offset)?;
+    }
+}
+
+/// Check `path` once, reopening it from the start if it was rotated or
+/// truncated, and print anything new since `offset`. Returns the new offset.
+fn follow_tick(path: &Path, identity: &mut FileIdentity, mut offset: u64) -> Result<u64> {
+    let meta = match metadata(path) {
+        Ok(meta) => meta,
+        Err(_) => return Ok(offset), // momentarily missing mid-rotation; retry next tick
+    };
+
+    if meta.dev() != identity.dev || meta.ino() != identity.ino || meta.len() < offset {
+        info!("{} was rotated or truncated, reopening", path.display());
+        *identity = FileIdentity {
+            dev: meta.dev(),
+            ino: meta.ino(),
+        };
+        offset = 0;
+    }
+
+    if meta.len() > offset {
+        let mut file =
+            File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        file.seek(SeekFrom::Start(offset))
+            .with_context(|| format!("failed to seek {}", path.display()))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        stdout()
+            .write_all(&buf)
+            .context("failed to write tail output to stdout")?;
+        offset += buf.len() as u64;
+    }
+
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use tempfile::{NamedTempFile, tempdir};
+
+    #[test]
+    fn test_follow_tick_reads_appended_data() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"hello\n").unwrap();
+        let path = temp_file.path();
+        let mut identity = FileIdentity::of(path).unwrap();
+
+        let offset = follow_tick(path, &mut identity, 0).unwrap();
+        assert_eq!(offset, 6);
+
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(b"world\n").unwrap();
+
+        let offset = follow_tick(path, &mut identity, offset).unwrap();
+        assert_eq!(offset, 12);
+    }
+
+    #[test]
+    fn test_follow_tick_detects_rotation() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, b"before rotation\n").unwrap();
+        let mut identity = FileIdentity::of(&path).unwrap();
+        let offset = follow_tick(&path, &mut identity, 0).unwrap();
+
+        // Simulate logrotate: move the old file aside and create a new one
+        // in its place, so the path keeps its name but gets a fresh inode.
+        std::fs::rename(&path, dir.path().join("app.log.1")).unwrap();
+        std::fs::write(&path, b"after rotation\n").unwrap();
+
+        let offset = follow_tick(&path, &mut identity, offset).unwrap();
+        assert_eq!(
+            offset,
+            "after rotation\n".len() as u64,
+            "rotation should reopen the file from the start"
+        );
+    }
+
+    #[test]
+    fn test_follow_tick_detects_truncation() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"0123456789").unwrap();
+        let path = temp_file.path();
+        let mut identity = FileIdentity::of(path).unwrap();
+        let offset = follow_tick(path, &mut identity, 0).unwrap();
+        assert_eq!(offset, 10);
+
+        let file = OpenOptions::new().write(true).open(path).unwrap();
+        file.set_len(0).unwrap();
+        std::fs::write(path, b"abc").unwrap();
+
+        let offset = follow_tick(path, &mut identity, offset).unwrap();
+        assert_eq!(offset, 3, "truncation should reopen the file from the start");
+    }
+}
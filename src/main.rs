@@ -1,9 +1,30 @@
-use anyhow::{Context, Result, bail};
+mod error;
+mod follow;
+mod remote;
+
+use anyhow::{Context, Result, anyhow};
 use clap::Parser;
-use log::{error, info, warn};
-use std::fs::{File, metadata};
+use log::{info, warn};
+use nix::errno::Errno;
+use nix::fcntl::AtFlags;
+use nix::sys::stat;
+use nix::unistd;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write, stdout};
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, ExitCode, ExitStatus};
+use std::thread;
+use std::time::Duration;
+
+use error::TailorError;
+
+/// Number of bytes read per backward seek when scanning for the tail offset.
+const TAIL_CHUNK_SIZE: usize = 8192;
+
+/// How long to wait between an EOF and reopening the file in [`stream_tail`]'s
+/// follow loop, matching `follow.rs`'s poll cadence so a stream that returns
+/// immediate EOF (e.g. `/dev/null`) doesn't busy-spin a CPU core.
+const STREAM_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 /// tail a file or execite the fallback command
 ///
@@ -14,74 +35,425 @@ use std::process::Command;
 ///   tailor file.txt touch                         # tail file.txt, or touch file.txt
 ///   tailor file.txt chmod 755                     # tail file.txt, or chmod 755 file.txt
 ///   tailor config.json cp config.template.json    # tail file.txt, or cp config.template.json config.json
+///   tailor -n 5 file.txt touch                    # tail the last 5 lines of file.txt, or touch file.txt
+///   tailor -c 512 file.txt touch                  # tail the last 512 bytes of file.txt, or touch file.txt
+///   tailor -f app.log touch                       # tail app.log and keep streaming new lines, or touch it
+///   tailor user@host:/var/log/app.log touch       # tail app.log on host over SSH, or touch it there
+///   tailor --atomic config.json cp config.template.json  # atomically replace config.json via a temp file
+///   tailor --capture file.txt touch               # log the fallback's stdout/stderr instead of inheriting them
 #[derive(Parser, Debug)]
 #[command(version, about, long_about, verbatim_doc_comment)]
 struct Args {
     file: String,
 
+    #[arg(
+        short = 'n',
+        long = "lines",
+        default_value_t = 10,
+        help = "Output the last N lines"
+    )]
+    lines: u64,
+
+    #[arg(
+        short = 'c',
+        long = "bytes",
+        conflicts_with = "lines",
+        help = "Output the last N bytes instead of lines"
+    )]
+    bytes: Option<u64>,
+
+    #[arg(
+        short = 'f',
+        long = "follow",
+        help = "Keep streaming data as the file grows, following rotation"
+    )]
+    follow: bool,
+
+    #[arg(
+        long = "no-dereference",
+        help = "Classify a symlink itself instead of the file it points to"
+    )]
+    no_dereference: bool,
+
+    #[arg(
+        long = "atomic",
+        help = "Have the fallback command write a sibling temp file, then rename it into place on success"
+    )]
+    atomic: bool,
+
+    #[arg(
+        long = "capture",
+        help = "Capture the fallback command's stdout/stderr and log them instead of inheriting the terminal"
+    )]
+    capture: bool,
+
     #[arg(trailing_var_arg = true, num_args = 0.., help = "Command to run if file can't be tailed")]
     command: Vec<String>,
 }
 
-fn main() -> Result<()> {
+/// How far back from the end of the file a tail operation should read.
+pub(crate) enum TailMode {
+    Lines(u64),
+    Bytes(u64),
+}
+
+fn main() -> ExitCode {
     env_logger::init();
 
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            e.report();
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+fn run() -> Result<(), TailorError> {
     let args = Args::parse();
 
-    if can_tail_file(&args.file)? {
-        run_command("tail", &[&args.file])?;
+    if let Some(target) = remote::RemoteTarget::parse(&args.file) {
+        return run_remote(&args, &target);
+    }
+
+    match can_tail_file(&args.file, !args.no_dereference)? {
+        Tailable::Regular => {
+            let mode = match args.bytes {
+                Some(n) => TailMode::Bytes(n),
+                None => TailMode::Lines(args.lines),
+            };
+            let offset = tail_file(&args.file, mode)?;
+            if args.follow {
+                follow::follow_file(&args.file, offset)?;
+            }
+        }
+        Tailable::Stream => stream_tail(&args.file, args.follow)?,
+        Tailable::Other => {
+            if args.command.is_empty() {
+                return Err(TailorError::Usage(anyhow!(
+                    "File '{}' is not readable and no fallback command specified.",
+                    args.file
+                )));
+            }
+
+            let temp_path = args.atomic.then(|| atomic_temp_path(&args.file));
+            let target_arg = temp_path.as_deref().unwrap_or(&args.file);
+
+            let mut command_args: Vec<&str> =
+                args.command[1..].iter().map(|s| s.as_str()).collect();
+            command_args.push(target_arg);
+            info!(
+                "file {} cannot be tailed, executing: {} {}",
+                args.file,
+                &args.command[0],
+                command_args.join(" ")
+            );
+
+            let status = if args.capture {
+                run_command_captured(&args.command[0], &command_args)?
+            } else {
+                run_command(&args.command[0], &command_args)?
+            };
+            if !status.success() {
+                if let Some(temp_path) = &temp_path {
+                    // Best-effort: don't let a leftover temp file from a
+                    // failed fallback linger in the target directory.
+                    let _ = std::fs::remove_file(temp_path);
+                }
+                return Err(TailorError::Fallback(status.code().unwrap_or(1)));
+            }
+
+            if let Some(temp_path) = temp_path {
+                std::fs::rename(&temp_path, &args.file).with_context(|| {
+                    format!(
+                        "failed to move fallback output from {temp_path} into {}",
+                        args.file
+                    )
+                })?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Path for the sibling temp file `--atomic` directs the fallback command to
+/// write, in the same directory as `file_path` so the final `rename(2)` stays
+/// on one filesystem and is therefore atomic.
+fn atomic_temp_path(file_path: &str) -> String {
+    let path = Path::new(file_path);
+    let file_name = path
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or("tailor-fallback");
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    dir.join(format!(".{file_name}.tailor-tmp.{}", std::process::id()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn run_remote(args: &Args, target: &remote::RemoteTarget) -> Result<(), TailorError> {
+    let session = remote::connect(target)?;
+
+    if remote::can_tail_remote(&session, &target.path)? {
+        let mode = match args.bytes {
+            Some(n) => TailMode::Bytes(n),
+            None => TailMode::Lines(args.lines),
+        };
+        let offset = remote::tail_remote(&session, &target.path, mode)?;
+        if args.follow {
+            remote::follow_remote(&session, &target.path, offset)?;
+        }
     } else {
         if args.command.is_empty() {
-            bail!(
-                "File '{}' is not readable and no fallback command specified.",
-                args.file
-            );
+            return Err(TailorError::Usage(anyhow!(
+                "Remote file '{}' on {} is not readable and no fallback command specified.",
+                target.path,
+                target.host
+            )));
+        }
+        if args.atomic || args.capture {
+            return Err(TailorError::Usage(anyhow!(
+                "--atomic and --capture are not supported for remote fallback commands (target: {}:{})",
+                target.host,
+                target.path
+            )));
         }
-        let mut command_args: Vec<&str> = args.command[1..].iter().map(|s| s.as_str()).collect();
-        command_args.push(args.file.as_str());
+        let command_args: Vec<&str> = args.command[1..].iter().map(|s| s.as_str()).collect();
         info!(
-            "file {} cannot be tailed, executing: {} {}",
-            args.file,
+            "remote file {}:{} cannot be tailed, executing remotely: {} {}",
+            target.host,
+            target.path,
             &args.command[0],
             command_args.join(" ")
         );
-        run_command(&args.command[0], &command_args)?;
+        let exit_code =
+            remote::run_remote_command(&session, &args.command[0], &command_args, &target.path)?;
+        if exit_code != 0 {
+            return Err(TailorError::Fallback(exit_code));
+        }
     }
 
     Ok(())
 }
 
-fn can_tail_file(file_path: &str) -> Result<bool> {
-    if !Path::new(file_path).exists() {
-        return Ok(false);
+/// Whether, and how, a path can be tailed.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Tailable {
+    /// A regular (or block device) file: supports seeking to the end.
+    Regular,
+    /// A FIFO or character device: has no meaningful end to seek to, so it
+    /// can only be read forward from wherever it's currently positioned.
+    Stream,
+    /// Missing, a directory, a socket, unreadable, or otherwise not
+    /// something `tail` can make sense of.
+    Other,
+}
+
+/// Classify `file_path` using direct syscalls rather than `File::open`, so
+/// special files (FIFOs, sockets, devices) aren't opened just to test
+/// readability. `follow_symlinks` controls whether a symlink is classified
+/// by its target (the usual behavior) or treated as its own entry.
+fn can_tail_file(file_path: &str, follow_symlinks: bool) -> Result<Tailable> {
+    let file_stat = match if follow_symlinks {
+        stat::stat(file_path)
+    } else {
+        stat::lstat(file_path)
+    } {
+        Ok(s) => s,
+        Err(Errno::ENOENT) => return Ok(Tailable::Other),
+        Err(e) => {
+            return Err(e).with_context(|| format!("failed to stat {file_path}"));
+        }
+    };
+
+    // The file type is a field under S_IFMT, not independent bits: compare it
+    // whole, since e.g. S_IFBLK and S_IFSOCK share bits with S_IFDIR.
+    let kind = stat::SFlag::from_bits_truncate(file_stat.st_mode) & stat::SFlag::S_IFMT;
+
+    if kind == stat::SFlag::S_IFLNK {
+        warn!("{file_path} is a symlink and --no-dereference was given");
+        return Ok(Tailable::Other);
     }
-    let meta =
-        metadata(file_path).with_context(|| format!("failed to read metadata for {file_path}"))?;
-    if meta.is_dir() {
+
+    let access_flags = if follow_symlinks {
+        AtFlags::empty()
+    } else {
+        AtFlags::AT_SYMLINK_NOFOLLOW
+    };
+    if let Err(e) = unistd::faccessat(None, file_path, unistd::AccessFlags::R_OK, access_flags) {
+        warn!("cannot read file {file_path}: {e}");
+        return Ok(Tailable::Other);
+    }
+
+    if kind == stat::SFlag::S_IFDIR {
         warn!("{file_path} is a directory");
-        return Ok(false);
+        Ok(Tailable::Other)
+    } else if kind == stat::SFlag::S_IFREG || kind == stat::SFlag::S_IFBLK {
+        Ok(Tailable::Regular)
+    } else if kind == stat::SFlag::S_IFIFO || kind == stat::SFlag::S_IFCHR {
+        Ok(Tailable::Stream)
+    } else {
+        warn!("{file_path} is a socket or other special file that can't be tailed");
+        Ok(Tailable::Other)
     }
-    match File::open(file_path) {
-        Ok(_) => Ok(true),
-        Err(e) => {
-            warn!("cannot read file {file_path}: {e}");
-            Ok(false)
+}
+
+/// Read `file_path` forward from wherever it currently is until EOF, the way
+/// a FIFO or character device must be tailed since it can't be seeked to the
+/// end. With `follow`, reopen and keep reading once EOF is hit, waiting
+/// [`STREAM_POLL_INTERVAL`] beforehand so a device that returns EOF
+/// immediately instead of blocking (e.g. `/dev/null`) doesn't spin a CPU core.
+fn stream_tail(file_path: &str, follow: bool) -> Result<()> {
+    loop {
+        let mut file =
+            File::open(file_path).with_context(|| format!("failed to open {file_path}"))?;
+        let mut buf = [0u8; TAIL_CHUNK_SIZE];
+        loop {
+            let n = file
+                .read(&mut buf)
+                .with_context(|| format!("failed to read {file_path}"))?;
+            if n == 0 {
+                break;
+            }
+            stdout()
+                .write_all(&buf[..n])
+                .context("failed to write tail output to stdout")?;
+        }
+        if !follow {
+            return Ok(());
         }
+        thread::sleep(STREAM_POLL_INTERVAL);
     }
 }
 
-fn run_command(command: &str, args: &[&str]) -> Result<()> {
+/// Print the tail of `file_path` to stdout without reading the whole file into
+/// memory, returning the file's length (the offset follow mode should resume
+/// reading from).
+fn tail_file(file_path: &str, mode: TailMode) -> Result<u64> {
+    let mut file = File::open(file_path).with_context(|| format!("failed to open {file_path}"))?;
+    tail_reader(&mut file, mode).with_context(|| format!("failed to tail {file_path}"))
+}
+
+/// Print the tail of `reader` to stdout without reading the whole thing into
+/// memory, returning its length (the offset follow mode should resume reading
+/// from). Generic over anything seekable, not just [`File`], so a remote SFTP
+/// handle can be tailed with the exact same scanning logic as a local file.
+pub(crate) fn tail_reader<R: Read + Seek>(reader: &mut R, mode: TailMode) -> Result<u64> {
+    let file_len = reader.seek(SeekFrom::End(0)).context("failed to seek")?;
+
+    let start_offset = match mode {
+        TailMode::Bytes(n) => file_len.saturating_sub(n),
+        TailMode::Lines(n) => {
+            tail_line_offset(reader, file_len, n).context("failed to scan for tail offset")?
+        }
+    };
+
+    reader
+        .seek(SeekFrom::Start(start_offset))
+        .context("failed to seek")?;
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf).context("failed to read")?;
+    stdout()
+        .write_all(&buf)
+        .context("failed to write tail output to stdout")?;
+    Ok(file_len)
+}
+
+/// Scan `reader` backward in fixed-size chunks to find the byte offset at
+/// which the last `n_lines` lines begin. A trailing newline at the very end
+/// of the file terminates the last line rather than starting a new one, so it
+/// is not counted as a line boundary; a final line with no trailing newline
+/// still counts as a line.
+pub(crate) fn tail_line_offset<R: Read + Seek>(
+    file: &mut R,
+    file_len: u64,
+    n_lines: u64,
+) -> Result<u64> {
+    if n_lines == 0 || file_len == 0 {
+        return Ok(file_len);
+    }
+
+    let mut newlines_seen = 0u64;
+    let mut pos = file_len;
+    let mut buf = [0u8; TAIL_CHUNK_SIZE];
+
+    while pos > 0 {
+        let chunk_len = (TAIL_CHUNK_SIZE as u64).min(pos) as usize;
+        pos -= chunk_len as u64;
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut buf[..chunk_len])?;
+
+        for i in (0..chunk_len).rev() {
+            if buf[i] != b'\n' {
+                continue;
+            }
+            let abs_pos = pos + i as u64;
+            if abs_pos == file_len - 1 {
+                continue;
+            }
+            newlines_seen += 1;
+            if newlines_seen == n_lines {
+                return Ok(abs_pos + 1);
+            }
+        }
+    }
+
+    Ok(0)
+}
+
+fn run_command(command: &str, args: &[&str]) -> Result<ExitStatus> {
     let mut cmd = Command::new(command);
     cmd.args(args);
     let status = cmd
         .status()
         .with_context(|| format!("failed to execute command '{command}'"))?;
     if !status.success() {
-        let exit_code = status.code().unwrap_or(-1);
-        error!("command '{command}' failed with exit code: {exit_code}");
-        std::process::exit(exit_code);
+        warn!(
+            "command '{command}' failed with exit code: {}",
+            status.code().unwrap_or(-1)
+        );
     }
-    Ok(())
+    Ok(status)
+}
+
+/// Captured stdout/stderr from a fallback command run with `--capture`.
+struct CommandOutput {
+    stdout: String,
+    stderr: String,
+}
+
+/// Like [`run_command`], but pipes the child's stdout/stderr instead of
+/// inheriting the terminal, so they don't interleave with tail output, and
+/// logs them through the usual `log` facade instead.
+fn run_command_captured(command: &str, args: &[&str]) -> Result<ExitStatus> {
+    let mut cmd = Command::new(command);
+    cmd.args(args);
+    let output = cmd
+        .output()
+        .with_context(|| format!("failed to execute command '{command}'"))?;
+
+    let captured = CommandOutput {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    };
+    if !captured.stdout.trim_end().is_empty() {
+        info!("{command} stdout: {}", captured.stdout.trim_end());
+    }
+    if !captured.stderr.trim_end().is_empty() {
+        warn!("{command} stderr: {}", captured.stderr.trim_end());
+    }
+    if !output.status.success() {
+        warn!(
+            "command '{command}' failed with exit code: {}",
+            output.status.code().unwrap_or(-1)
+        );
+    }
+
+    Ok(output.status)
 }
 
 #[cfg(test)]
@@ -98,22 +470,34 @@ mod tests {
     fn test_can_tail_file_with_existing_file() {
         let temp_file = NamedTempFile::new().unwrap();
         let file_path = temp_file.path().to_str().unwrap();
-        let result = can_tail_file(file_path).unwrap();
-        assert!(result, "Should be able to tail an existing readable file");
+        let result = can_tail_file(file_path, true).unwrap();
+        assert_eq!(
+            result,
+            Tailable::Regular,
+            "Should be able to tail an existing readable file"
+        );
     }
 
     #[test]
     fn test_can_tail_file_with_nonexistent_file() {
-        let result = can_tail_file("/tmp/nonexistent_file_12345").unwrap();
-        assert!(!result, "Should not be able to tail a nonexistent file");
+        let result = can_tail_file("/tmp/nonexistent_file_12345", true).unwrap();
+        assert_eq!(
+            result,
+            Tailable::Other,
+            "Should not be able to tail a nonexistent file"
+        );
     }
 
     #[test]
     fn test_can_tail_file_with_directory() {
         let temp_dir = tempdir().unwrap();
         let dir_path = temp_dir.path().to_str().unwrap();
-        let result = can_tail_file(dir_path).unwrap();
-        assert!(!result, "Should not be able to tail a directory");
+        let result = can_tail_file(dir_path, true).unwrap();
+        assert_eq!(
+            result,
+            Tailable::Other,
+            "Should not be able to tail a directory"
+        );
     }
 
     #[test]
@@ -125,14 +509,108 @@ mod tests {
         perms.set_mode(0o000);
         fs::set_permissions(file_path, perms).unwrap();
 
-        let result = can_tail_file(file_path).unwrap();
-        assert!(!result, "Should not be able to tail an unreadable file");
+        let result = can_tail_file(file_path, true).unwrap();
+        assert_eq!(
+            result,
+            Tailable::Other,
+            "Should not be able to tail an unreadable file"
+        );
 
         let mut perms = fs::metadata(file_path).unwrap().permissions();
         perms.set_mode(0o644);
         fs::set_permissions(file_path, perms).unwrap();
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_can_tail_file_with_fifo() {
+        let temp_dir = tempdir().unwrap();
+        let fifo_path = temp_dir.path().join("myfifo");
+        nix::unistd::mkfifo(&fifo_path, nix::sys::stat::Mode::S_IRUSR | nix::sys::stat::Mode::S_IWUSR)
+            .unwrap();
+
+        let result = can_tail_file(fifo_path.to_str().unwrap(), true).unwrap();
+        assert_eq!(
+            result,
+            Tailable::Stream,
+            "A FIFO can only be tailed as a stream"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_can_tail_file_with_symlink_no_dereference() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let temp_dir = tempdir().unwrap();
+        let link_path = temp_dir.path().join("link");
+        std::os::unix::fs::symlink(temp_file.path(), &link_path).unwrap();
+
+        let dereferenced = can_tail_file(link_path.to_str().unwrap(), true).unwrap();
+        assert_eq!(dereferenced, Tailable::Regular);
+
+        let not_dereferenced = can_tail_file(link_path.to_str().unwrap(), false).unwrap();
+        assert_eq!(not_dereferenced, Tailable::Other);
+    }
+
+    fn write_temp_file(contents: &str) -> NamedTempFile {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(contents.as_bytes()).unwrap();
+        temp_file
+    }
+
+    #[test]
+    fn test_tail_file_lines_with_trailing_newline() {
+        let temp_file = write_temp_file("a\nb\nc\n");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let file_len = tail_file(file_path, TailMode::Lines(2)).unwrap();
+        assert_eq!(file_len, 6, "tail_file should report the file's full length");
+        let offset =
+            tail_line_offset(&mut File::open(file_path).unwrap(), 6, 2).unwrap();
+        assert_eq!(offset, 2, "tail of 2 lines should start after 'a\\n'");
+    }
+
+    #[test]
+    fn test_tail_file_lines_without_trailing_newline() {
+        let temp_file = write_temp_file("a\nb\nc");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let offset =
+            tail_line_offset(&mut File::open(file_path).unwrap(), 5, 2).unwrap();
+        assert_eq!(
+            offset, 2,
+            "a final line without a trailing newline should still count"
+        );
+    }
+
+    #[test]
+    fn test_tail_file_lines_more_than_available() {
+        let temp_file = write_temp_file("a\nb\n");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let offset =
+            tail_line_offset(&mut File::open(file_path).unwrap(), 4, 100).unwrap();
+        assert_eq!(offset, 0, "requesting more lines than exist should return the start of the file");
+    }
+
+    #[test]
+    fn test_tail_file_empty_file() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let offset = tail_line_offset(&mut File::open(file_path).unwrap(), 0, 10).unwrap();
+        assert_eq!(offset, 0, "an empty file has no lines to tail");
+    }
+
+    #[test]
+    fn test_tail_file_bytes_mode_succeeds() {
+        let temp_file = write_temp_file("0123456789");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        assert!(tail_file(file_path, TailMode::Bytes(4)).is_ok());
+        assert!(tail_file(file_path, TailMode::Bytes(100)).is_ok());
+    }
+
     #[test]
     fn test_run_command_success() {
         let result = run_command("true", &[]);
@@ -204,12 +682,62 @@ mod tests {
         assert!(result.is_err(), "Nonexistent command should return error");
     }
 
+    #[test]
+    fn test_run_command_captured_returns_success_status() {
+        let status = run_command_captured("echo", &["captured output"]).unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_atomic_temp_path_is_a_sibling_hidden_file() {
+        let temp_path = atomic_temp_path("/var/log/app.log");
+        let path = Path::new(&temp_path);
+        assert_eq!(path.parent(), Some(Path::new("/var/log")));
+        assert!(
+            path.file_name().unwrap().to_str().unwrap().starts_with(".app.log.tailor-tmp."),
+            "temp file should be a hidden sibling of the target"
+        );
+    }
+
+    #[test]
+    fn test_atomic_fallback_rename_into_place() {
+        let temp_dir = tempdir().unwrap();
+        let target = temp_dir.path().join("config.json");
+        assert!(!target.exists());
+
+        let mut cmd = Command::cargo_bin("tailor").unwrap();
+        cmd.arg("--atomic")
+            .arg(target.to_str().unwrap())
+            .arg("touch")
+            .assert()
+            .success();
+
+        assert!(target.exists(), "fallback output should be renamed into place");
+        let siblings: Vec<_> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(siblings.len(), 1, "no leftover temp file should remain");
+    }
+
     #[test]
     fn test_main_fails_without_fallback_command() {
         let mut cmd = Command::cargo_bin("tailor").unwrap();
         cmd.arg("/tmp/nonexistent_file_12345")
             .assert()
             .failure()
+            .code(1)
             .stderr(contains("no fallback command"));
     }
+
+    #[test]
+    fn test_main_propagates_fallback_exit_code() {
+        let mut cmd = Command::cargo_bin("tailor").unwrap();
+        cmd.arg("/tmp/nonexistent_file_for_exit_code_test")
+            .arg("sh")
+            .arg("-c")
+            .arg("exit 7")
+            .assert()
+            .code(7);
+    }
 }